@@ -0,0 +1,224 @@
+//! `#[derive(Tree)]` for `evco`.
+//!
+//! Turns an enum where fieldless variants are terminals and variants
+//! holding their children as a single `Vec<Self>` field are nonterminals
+//! into a full `evco::gp::Tree` implementation, removing the boilerplate of
+//! hand-writing `rand_terminal`/`rand_nonterminal`/`rand_replacement`.
+//!
+//! Modeled on the `GenRandom` derive pattern.
+//!
+//! ```ignore
+//! #[derive(Tree)]
+//! enum Expr {
+//!     Constant,
+//!     #[tree(arity = 2, weight = 2)]
+//!     Add(Vec<Expr>),
+//!     #[tree(arity = 2)]
+//!     Mul(Vec<Expr>),
+//! }
+//! ```
+
+#![deny(missing_docs, missing_debug_implementations, missing_copy_implementations,
+        trivial_casts, trivial_numeric_casts,
+        unsafe_code,
+        unused_import_braces, unused_qualifications)]
+
+extern crate proc_macro;
+extern crate syn;
+#[macro_use]
+extern crate quote;
+
+use proc_macro::TokenStream;
+
+/// Derive `evco::gp::Tree` for an enum.
+///
+/// Fieldless variants are terminals. A nonterminal variant must hold
+/// exactly one `Vec<Self>` field, its children, and must carry
+/// `#[tree(arity = N)]` to say how many children `rand_nonterminal` should
+/// grow for it. Any variant's weight relative to its siblings defaults to
+/// `1`, and can be overridden with `#[tree(weight = N)]` to bias operator
+/// selection. The macro also defines `Self::TERMINAL_PROPORTION`, the ratio
+/// of terminal to total variants.
+#[proc_macro_derive(Tree, attributes(tree))]
+pub fn derive_tree(input: TokenStream) -> TokenStream {
+    let source = input.to_string();
+    let ast = syn::parse_derive_input(&source).expect("#[derive(Tree)] failed to parse input");
+    impl_tree(&ast).parse().expect("#[derive(Tree)] failed to parse its own output")
+}
+
+fn impl_tree(ast: &syn::DeriveInput) -> quote::Tokens {
+    let name = &ast.ident;
+    let variants = match ast.body {
+        syn::Body::Enum(ref variants) => variants,
+        syn::Body::Struct(_) => panic!("#[derive(Tree)] only supports enums, not structs"),
+    };
+
+    let terminals: Vec<_> = variants.iter().filter(|variant| variant.data.fields().is_empty()).collect();
+    let nonterminals: Vec<_> =
+        variants.iter().filter(|variant| !variant.data.fields().is_empty()).collect();
+
+    // Without at least one of each, `rand_terminal`/`rand_nonterminal` would
+    // call `weighted_variant_picker` over an empty slice, whose `gen_range(0,
+    // 0)` panics with a message pointing at `rand` rather than the enum.
+    if terminals.is_empty() {
+        panic!("#[derive(Tree)]: `{}` has no fieldless variant to act as a terminal", name);
+    }
+    if nonterminals.is_empty() {
+        panic!("#[derive(Tree)]: `{}` has no variant with fields to act as a nonterminal", name);
+    }
+
+    let terminal_proportion = terminals.len() as f32 / variants.len() as f32;
+
+    let children_arms = nonterminals.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        quote! { #name::#variant_ident(ref children) => &children[..] }
+    });
+    let children_mut_arms = nonterminals.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        quote! { #name::#variant_ident(ref mut children) => &mut children[..] }
+    });
+    let terminal_children_arms = terminals.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        quote! { #name::#variant_ident => &[] }
+    });
+    let terminal_children_mut_arms = terminals.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        quote! { #name::#variant_ident => &mut [] }
+    });
+    let terminal_replacement_arms = terminals.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        quote! { #name::#variant_ident => Self::rand_terminal(rng) }
+    });
+
+    let rng_expr = quote! { rng };
+    let tg_rng_expr = quote! { tg.rng };
+
+    let rand_terminal_body = weighted_variant_picker(name, &terminals, &rng_expr, |_| quote! {});
+    let rand_nonterminal_body = weighted_variant_picker(name, &nonterminals, &tg_rng_expr, |variant| {
+        let arity = variant_arity(variant);
+        quote! {
+            ((0..#arity).map(|_| ::evco::gp::Tree::child(tg, current_depth + 1)).collect::<Vec<_>>())
+        }
+    });
+
+    let rand_replacement_arms = nonterminals.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let arity = variant_arity(variant);
+        let same_arity: Vec<_> =
+            nonterminals.iter().filter(|candidate| variant_arity(candidate) == arity).cloned().collect();
+        let picker = weighted_variant_picker(name, &same_arity, &rng_expr, |_| quote! { (children.clone()) });
+        quote! {
+            #name::#variant_ident(ref children) => {
+                #picker
+            }
+        }
+    });
+
+    quote! {
+        impl #name {
+            /// The proportion of this enum's variants that are terminals
+            /// (fieldless), as opposed to nonterminals.
+            pub const TERMINAL_PROPORTION: f32 = #terminal_proportion;
+        }
+
+        impl ::evco::gp::Tree for #name {
+            fn children(&self) -> &[Self] {
+                match *self {
+                    #(#children_arms,)*
+                    #(#terminal_children_arms,)*
+                }
+            }
+
+            fn children_mut(&mut self) -> &mut [Self] {
+                match *self {
+                    #(#children_mut_arms,)*
+                    #(#terminal_children_mut_arms,)*
+                }
+            }
+
+            fn rand_terminal<R: ::rand::Rng>(rng: &mut R) -> Self {
+                #rand_terminal_body
+            }
+
+            fn rand_nonterminal<R: ::rand::Rng>(tg: &mut ::evco::gp::TreeGen<R>, current_depth: usize) -> Self {
+                #rand_nonterminal_body
+            }
+
+            fn rand_replacement<R: ::rand::Rng>(&self, rng: &mut R) -> Self {
+                match *self {
+                    #(#rand_replacement_arms,)*
+                    #(#terminal_replacement_arms,)*
+                }
+            }
+        }
+    }
+}
+
+/// Build a `gen_range`-driven match that picks one of `variants`, weighted
+/// by each variant's `#[tree(weight = N)]` (default `1`), and constructs it
+/// via `build_variant`. `rng_expr` is the expression used to draw the
+/// pick — `rng` where a `&mut R` is already in scope, `tg.rng` where only
+/// a `&mut TreeGen<R>` is.
+fn weighted_variant_picker<F>(name: &syn::Ident,
+                               variants: &[&syn::Variant],
+                               rng_expr: &quote::Tokens,
+                               build_variant: F)
+                               -> quote::Tokens
+    where F: Fn(&syn::Variant) -> quote::Tokens
+{
+    let weights: Vec<u32> = variants.iter().map(|variant| variant_weight(variant)).collect();
+    let total_weight: u32 = weights.iter().sum();
+    let mut arms = Vec::with_capacity(variants.len());
+    let mut cumulative = 0u32;
+    for (variant, weight) in variants.iter().zip(weights.iter()) {
+        cumulative += *weight;
+        let variant_ident = &variant.ident;
+        let body = build_variant(variant);
+        arms.push(quote! { n if n < #cumulative => #name::#variant_ident #body });
+    }
+    quote! {
+        {
+            let chosen = #rng_expr.gen_range(0, #total_weight);
+            match chosen {
+                #(#arms,)*
+                _ => unreachable!("#[derive(Tree)] weights did not cover the full range"),
+            }
+        }
+    }
+}
+
+/// Read a `#[tree(...)]` integer value named `key`, if present.
+fn tree_attr_int(variant: &syn::Variant, key: &str) -> Option<u32> {
+    for attr in &variant.attrs {
+        if let syn::MetaItem::List(ref ident, ref items) = attr.value {
+            if ident == "tree" {
+                for item in items {
+                    if let syn::NestedMetaItem::MetaItem(syn::MetaItem::NameValue(ref name, syn::Lit::Int(value, _))) =
+                        *item {
+                        if name == key {
+                            return Some(value as u32);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Read a variant's `#[tree(weight = N)]`, defaulting to `1`.
+fn variant_weight(variant: &syn::Variant) -> u32 {
+    tree_attr_int(variant, "weight").unwrap_or(1)
+}
+
+/// Read a nonterminal variant's `#[tree(arity = N)]`.
+///
+/// # Panics
+///
+/// Panics if the attribute is missing: every nonterminal needs a fixed
+/// arity to know how many children `rand_nonterminal` should grow.
+fn variant_arity(variant: &syn::Variant) -> u32 {
+    tree_attr_int(variant, "arity")
+        .unwrap_or_else(|| panic!("#[derive(Tree)]: nonterminal variant `{}` needs #[tree(arity = N)]",
+                                   variant.ident))
+}
@@ -1,7 +1,9 @@
 use rand::Rng;
 
 /// The tree generation mode in use. See `TreeGen`.
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+// N.B. `Sized`'s `remaining_size` is an `f32`, so this can no longer derive
+// `Eq` (only `PartialEq`).
+#[derive(PartialEq, Clone, Copy, Debug)]
 pub enum TreeGenMode {
     /// Corresponds to `TreeGen::perfect`.
     Perfect(usize),
@@ -9,11 +11,25 @@ pub enum TreeGenMode {
     Full,
     /// Corresponds to `TreeGen::full_ranged`.
     FullRanged(usize),
+    /// Corresponds to `TreeGen::sized`. `remaining_size` is decremented by
+    /// `TreeGen::produced_nonterminal` as nonterminals are produced, while
+    /// `expected_branch_size` stays fixed for the lifetime of the `TreeGen`.
+    Sized {
+        /// Node "budget" left before a leaf is forced at any depth from
+        /// `min_depth` on; shallower than `min_depth`, nodes always branch.
+        remaining_size: f32,
+        /// The average number of children a nonterminal produces. Used
+        /// both to decide whether to keep branching and to decay
+        /// `remaining_size` as nonterminals are produced.
+        expected_branch_size: f32,
+    },
+    /// Corresponds to `TreeGen::tapered`.
+    Tapered,
 }
 
 /// Configure generation of trees. This manages tree depth by deciding when to
 /// generate a Terminal (leaf) node.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Debug)]
 pub struct TreeGen<'a, R>
     where R: 'a + Rng
 {
@@ -34,13 +50,13 @@ impl<'a, R> TreeGen<'a, R>
     /// [min_depth, max_depth].
     ///
     /// **This is the equivalent of DEAP's `genFull`.**
-    pub fn perfect(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<R> {
+    pub fn perfect(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<'_, R> {
         let chosen_depth = rng.gen_range(min_depth, max_depth + 1);
         TreeGen {
-            rng: rng,
+            rng,
             mode: TreeGenMode::Perfect(chosen_depth),
-            min_depth: min_depth,
-            max_depth: max_depth,
+            min_depth,
+            max_depth,
         }
     }
 
@@ -48,12 +64,12 @@ impl<'a, R> TreeGen<'a, R>
     /// linearly distributed between min_depth and a chosen depth in the range.
     ///
     /// **This is NOT the same as DEAP's `genFull`. See `TreeGen::full`**
-    pub fn full(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<R> {
+    pub fn full(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<'_, R> {
         TreeGen {
-            rng: rng,
+            rng,
             mode: TreeGenMode::Full,
-            min_depth: min_depth,
-            max_depth: max_depth,
+            min_depth,
+            max_depth,
         }
     }
 
@@ -61,13 +77,50 @@ impl<'a, R> TreeGen<'a, R>
     /// linearly distributed between min_depth and a chosen depth in the range.
     ///
     /// **This is the equivalent of DEAP's `genGrow`.**
-    pub fn full_ranged(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<R> {
+    pub fn full_ranged(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<'_, R> {
         let chosen_depth = rng.gen_range(min_depth, max_depth + 1);
         TreeGen {
-            rng: rng,
+            rng,
             mode: TreeGenMode::FullRanged(chosen_depth),
-            min_depth: min_depth,
-            max_depth: max_depth,
+            min_depth,
+            max_depth,
+        }
+    }
+
+    /// Generate a tree whose expected total node count is approximately
+    /// `desired_size`, regardless of depth. `expected_branch_size` is the
+    /// average number of children a nonterminal produces for your `Tree`,
+    /// used to decay the remaining budget as nonterminals are produced.
+    ///
+    /// **Inspired by proptest's `prop_recursive`.**
+    pub fn sized(rng: &mut R,
+                 min_depth: usize,
+                 max_depth: usize,
+                 desired_size: usize,
+                 expected_branch_size: f32)
+                 -> TreeGen<'_, R> {
+        TreeGen {
+            rng,
+            mode: TreeGenMode::Sized {
+                remaining_size: desired_size as f32,
+                expected_branch_size,
+            },
+            min_depth,
+            max_depth,
+        }
+    }
+
+    /// Generate a tree whose probability of branching decreases linearly
+    /// with depth, reaching `0` at `max_depth`, so trees thin out toward
+    /// their leaves instead of ending abruptly.
+    ///
+    /// **After Fuchsia's `EntryDistribution`.**
+    pub fn tapered(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<'_, R> {
+        TreeGen {
+            rng,
+            mode: TreeGenMode::Tapered,
+            min_depth,
+            max_depth,
         }
     }
 
@@ -75,7 +128,7 @@ impl<'a, R> TreeGen<'a, R>
     ///
     /// **This is the equivalent of DEAP's `genHalfAndHalf`.**
     // N.B. If TreeGen is ever Clone the random choice needs revising.
-    pub fn half_and_half(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<R> {
+    pub fn half_and_half(rng: &mut R, min_depth: usize, max_depth: usize) -> TreeGen<'_, R> {
         if rng.gen() {
             Self::perfect(rng, min_depth, max_depth)
         } else {
@@ -86,7 +139,10 @@ impl<'a, R> TreeGen<'a, R>
     /// Chooses whether to generate a Leaf node. Used by `Tree::child`.
     pub fn have_reached_a_leaf(&mut self, current_depth: usize) -> bool {
         match self.mode {
-            TreeGenMode::Perfect(chosen_depth) => current_depth == chosen_depth,
+            // `>=` rather than `==`, so that generation started from a depth that has already
+            // passed `chosen_depth` (e.g. `subtree_mutate` regrowing a deep node) still
+            // terminates instead of recursing past `max_depth`.
+            TreeGenMode::Perfect(chosen_depth) => current_depth >= chosen_depth || current_depth >= self.max_depth,
             TreeGenMode::Full => {
                 // This given an equal 1-in-depth_interval chance at every intermediary depth.
                 // Earlier checks ensure in the (1/depth)*(depth-1) case we reach chosen_depth,
@@ -99,12 +155,39 @@ impl<'a, R> TreeGen<'a, R>
             TreeGenMode::FullRanged(chosen_depth) => {
                 // This given an equal 1-in-depth_interval chance at every intermediary depth.
                 // Earlier checks ensure in the (1/depth)*(depth-1) case we reach chosen_depth,
-                // we do finally place a Leaf.
+                // we do finally place a Leaf. `>=` rather than `==` for the same reason as
+                // `Perfect` above.
                 let depth_interval = chosen_depth - self.min_depth;
                 // @TODO: Avoid converting depth_interval.
-                current_depth == chosen_depth ||
+                current_depth >= chosen_depth || current_depth >= self.max_depth ||
                 (current_depth >= self.min_depth) && self.gen_weighted_bool(depth_interval as u32)
             }
+            TreeGenMode::Sized { remaining_size, expected_branch_size } => {
+                current_depth == self.max_depth ||
+                (current_depth >= self.min_depth &&
+                 (remaining_size <= 0.0 ||
+                  self.gen::<f32>() >= remaining_size / (remaining_size + expected_branch_size)))
+            }
+            TreeGenMode::Tapered => {
+                // The branch probability falls linearly from 1 at min_depth to 0 at
+                // max_depth, so trees taper off towards their leaves.
+                current_depth >= self.max_depth ||
+                (current_depth >= self.min_depth && {
+                    let depth_interval = (self.max_depth - self.min_depth) as f32;
+                    let branch_probability = (self.max_depth - current_depth) as f32 / depth_interval;
+                    self.gen::<f32>() >= branch_probability
+                })
+            }
+        }
+    }
+
+    /// Account for a just-produced nonterminal against a
+    /// `TreeGenMode::Sized` budget. A no-op for the other modes.
+    ///
+    /// Called by `Tree::child` whenever it generates a nonterminal.
+    pub fn produced_nonterminal(&mut self) {
+        if let TreeGenMode::Sized { ref mut remaining_size, expected_branch_size } = self.mode {
+            *remaining_size -= expected_branch_size;
         }
     }
 }
@@ -125,4 +208,35 @@ impl<'a, R> Rng for TreeGen<'a, R>
     fn fill_bytes(&mut self, dest: &mut [u8]) {
         self.rng.fill_bytes(dest)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gp::Tree;
+    use gp::test_support::TestTree;
+
+    #[test]
+    fn sized_respects_min_depth_even_when_budget_runs_out() {
+        // Regression test: a small `desired_size` relative to `expected_branch_size`
+        // used to exhaust the budget well before `min_depth`, forcing leaves early.
+        let mut rng = ::rand::weak_rng();
+        let min_depth = 5;
+        for _ in 0..200 {
+            let mut tg = TreeGen::sized(&mut rng, min_depth, 10, 1, 2.0);
+            let tree = TestTree::child(&mut tg, 0);
+            assert!(tree.depth() >= min_depth);
+        }
+    }
+
+    #[test]
+    fn tapered_respects_depth_bounds() {
+        let mut rng = ::rand::weak_rng();
+        let (min_depth, max_depth) = (1, 6);
+        for _ in 0..200 {
+            let mut tg = TreeGen::tapered(&mut rng, min_depth, max_depth);
+            let tree = TestTree::child(&mut tg, 0);
+            assert!(tree.depth() <= max_depth);
+        }
+    }
 }
\ No newline at end of file
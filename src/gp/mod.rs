@@ -0,0 +1,191 @@
+use rand::Rng;
+
+/// Tree generation, governing depth and shape. See `TreeGen`.
+pub mod gen;
+/// Subtree crossover.
+pub mod crossover;
+/// Subtree, point and hoist mutation.
+pub mod mutation;
+/// A generation of individuals.
+pub mod population;
+/// The `TestTree` fixture shared by this module's and its siblings' tests.
+#[cfg(test)]
+pub(crate) mod test_support;
+
+pub use self::gen::{TreeGen, TreeGenMode};
+pub use self::population::Population;
+
+/// A node in a Genetic Programming tree.
+///
+/// Implementors are typically enums: fieldless variants act as terminals
+/// (leaves), and variants holding further `Tree`s act as nonterminals
+/// (branches). `evco` drives generation, crossover and mutation entirely
+/// through this trait.
+pub trait Tree: Sized {
+    /// The child subtrees of this node, in evaluation order. Terminals
+    /// return an empty slice.
+    fn children(&self) -> &[Self];
+
+    /// Mutable access to the child subtrees of this node. See `children`.
+    fn children_mut(&mut self) -> &mut [Self];
+
+    /// Generate a random terminal (leaf) node.
+    fn rand_terminal<R: Rng>(rng: &mut R) -> Self;
+
+    /// Generate a random node with the same arity (child count) as `self`,
+    /// keeping `self`'s existing children.
+    ///
+    /// Used by `point_mutate` to swap a node's operator without disturbing
+    /// the rest of the tree.
+    fn rand_replacement<R: Rng>(&self, rng: &mut R) -> Self;
+
+    /// Generate a random nonterminal (branch) node. Implementations should
+    /// build each child with `Tree::child(tg, current_depth + 1)` so the
+    /// depth invariants `tg` enforces are respected.
+    fn rand_nonterminal<R: Rng>(tg: &mut TreeGen<R>, current_depth: usize) -> Self;
+
+    /// Generate a random node, deciding between `rand_terminal` and
+    /// `rand_nonterminal` using `tg`'s depth logic.
+    fn child<R: Rng>(tg: &mut TreeGen<R>, current_depth: usize) -> Self {
+        if tg.have_reached_a_leaf(current_depth) {
+            Self::rand_terminal(tg.rng)
+        } else {
+            tg.produced_nonterminal();
+            Self::rand_nonterminal(tg, current_depth)
+        }
+    }
+
+    /// The number of nodes in this tree, including itself.
+    ///
+    /// Nodes are addressed by a depth-first pre-order index, as used by
+    /// `get_node_mut`, `crossover` and the mutation operators.
+    fn count_nodes(&self) -> usize {
+        1 + self.children().iter().map(Tree::count_nodes).sum::<usize>()
+    }
+
+    /// Look up the node at `index`, using the same depth-first pre-order
+    /// numbering as `count_nodes` (`0` is this node itself).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= self.count_nodes()`.
+    fn get_node_mut(&mut self, index: usize) -> &mut Self {
+        if index == 0 {
+            return self;
+        }
+        let mut remaining = index - 1;
+        for child in self.children_mut() {
+            let child_count = child.count_nodes();
+            if remaining < child_count {
+                return child.get_node_mut(remaining);
+            }
+            remaining -= child_count;
+        }
+        panic!("node index out of bounds");
+    }
+
+    /// The depth of the deepest leaf below this node. A leaf itself has
+    /// depth `0`.
+    fn depth(&self) -> usize {
+        self.children().iter().map(Tree::depth).max().map_or(0, |max_child_depth| max_child_depth + 1)
+    }
+
+    /// The total number of nodes in this tree, including itself.
+    ///
+    /// Equivalent to `count_nodes`, which additionally gives every node a
+    /// pre-order index for `get_node_mut`.
+    fn size(&self) -> usize {
+        self.count_nodes()
+    }
+
+    /// Pre-order fold over every node in this tree, starting from `init`.
+    fn fold<B, F: FnMut(B, &Self) -> B>(&self, init: B, mut f: F) -> B {
+        fn go<T: Tree, B, F: FnMut(B, &T) -> B>(node: &T, acc: B, f: &mut F) -> B {
+            let acc = f(acc, node);
+            node.children().iter().fold(acc, |acc, child| go(child, acc, f))
+        }
+        go(self, init, &mut f)
+    }
+}
+
+/// The depth of the node addressed by `index` (see `Tree::get_node_mut`)
+/// below `root`, which itself is at depth `0`.
+///
+/// Shared by `crossover` and the mutation operators, which need to know how
+/// far a swapped-in subtree would sit from the root.
+pub(crate) fn node_depth<T: Tree>(root: &T, index: usize) -> usize {
+    if index == 0 {
+        return 0;
+    }
+    let mut remaining = index - 1;
+    for child in root.children() {
+        let child_count = child.count_nodes();
+        if remaining < child_count {
+            return 1 + node_depth(child, remaining);
+        }
+        remaining -= child_count;
+    }
+    panic!("node index out of bounds");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gp::test_support::TestTree;
+
+    // A fixed, hand-built tree rather than a randomly generated one, so the
+    // expected depth/size/fold/node-order are known exactly:
+    //
+    //        Branch
+    //       /      \
+    //    Branch    Leaf
+    //    /    \
+    //  Leaf  Leaf
+    fn fixture() -> TestTree {
+        TestTree::Branch(vec![
+            TestTree::Branch(vec![TestTree::Leaf, TestTree::Leaf]),
+            TestTree::Leaf,
+        ])
+    }
+
+    #[test]
+    fn leaf_has_depth_and_size_zero_and_one() {
+        assert_eq!(TestTree::Leaf.depth(), 0);
+        assert_eq!(TestTree::Leaf.size(), 1);
+        assert_eq!(TestTree::Leaf.count_nodes(), 1);
+    }
+
+    #[test]
+    fn depth_is_the_deepest_leaf() {
+        assert_eq!(fixture().depth(), 2);
+    }
+
+    #[test]
+    fn size_counts_every_node() {
+        let tree = fixture();
+        assert_eq!(tree.size(), 5);
+        assert_eq!(tree.size(), tree.count_nodes());
+    }
+
+    #[test]
+    fn fold_visits_every_node_pre_order() {
+        let tree = fixture();
+        let branch_count = tree.fold(0, |acc, node| {
+            acc + if let TestTree::Branch(_) = *node { 1 } else { 0 }
+        });
+        assert_eq!(branch_count, 2);
+
+        let visited = tree.fold(0, |acc, _node| acc + 1);
+        assert_eq!(visited, tree.count_nodes());
+    }
+
+    #[test]
+    fn get_node_mut_uses_pre_order_indices() {
+        let mut tree = fixture();
+        assert!(matches!(tree.get_node_mut(0), TestTree::Branch(_)));
+        assert!(matches!(tree.get_node_mut(1), TestTree::Branch(_)));
+        assert!(matches!(tree.get_node_mut(2), TestTree::Leaf));
+        assert!(matches!(tree.get_node_mut(3), TestTree::Leaf));
+        assert!(matches!(tree.get_node_mut(4), TestTree::Leaf));
+    }
+}
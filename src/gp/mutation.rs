@@ -0,0 +1,89 @@
+use rand::Rng;
+
+use gp::{node_depth, Tree, TreeGen};
+
+/// Replace a randomly-chosen node with a freshly generated subtree.
+///
+/// The replacement is grown using `tg`'s existing depth logic, rooted at
+/// the chosen node's depth, so the resulting tree still respects `tg`'s
+/// depth bounds.
+///
+/// **This is the equivalent of DEAP's `mutUniform`.**
+pub fn subtree_mutate<T, R>(tree: &mut T, tg: &mut TreeGen<R>)
+    where T: Tree,
+          R: Rng
+{
+    let index = tg.rng.gen_range(0, tree.count_nodes());
+    let depth = node_depth(tree, index);
+    *tree.get_node_mut(index) = T::child(tg, depth);
+}
+
+/// Replace a single node's operator with another of the same arity,
+/// keeping its children unchanged.
+///
+/// **This is the equivalent of DEAP's `mutNodeReplacement`.**
+pub fn point_mutate<T, R>(tree: &mut T, rng: &mut R)
+    where T: Tree,
+          R: Rng
+{
+    let index = rng.gen_range(0, tree.count_nodes());
+    let node = tree.get_node_mut(index);
+    let replacement = node.rand_replacement(rng);
+    *node = replacement;
+}
+
+/// Replace the whole tree with one of its own randomly-chosen subtrees, to
+/// fight bloat.
+///
+/// **This is the equivalent of DEAP's `mutShrink`.**
+pub fn hoist_mutate<T, R>(tree: &mut T, rng: &mut R)
+    where T: Tree + Clone,
+          R: Rng
+{
+    let index = rng.gen_range(0, tree.count_nodes());
+    let hoisted = tree.get_node_mut(index).clone();
+    *tree = hoisted;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gp::TreeGen;
+    use gp::test_support::TestTree;
+
+    #[test]
+    fn subtree_mutate_respects_max_depth_from_any_starting_point() {
+        // Regression test: a fresh `TreeGen::perfect`/`full_ranged` per mutation
+        // (as population.rs's doc comment recommends for varied depths) used to
+        // overflow the stack once `depth` had already passed `chosen_depth`.
+        let mut rng = ::rand::weak_rng();
+        let max_depth = 5;
+        for _ in 0..200 {
+            let mut tg = TreeGen::full_ranged(&mut rng, 1, max_depth);
+            let mut tree = TestTree::child(&mut tg, 0);
+            let mut tg = TreeGen::perfect(&mut rng, 1, max_depth);
+            subtree_mutate(&mut tree, &mut tg);
+            assert!(tree.depth() <= max_depth);
+        }
+    }
+
+    #[test]
+    fn point_mutate_preserves_node_count() {
+        let mut rng = ::rand::weak_rng();
+        let mut tg = TreeGen::full_ranged(&mut rng, 1, 4);
+        let mut tree = TestTree::child(&mut tg, 0);
+        let before = tree.count_nodes();
+        point_mutate(&mut tree, &mut rng);
+        assert_eq!(tree.count_nodes(), before);
+    }
+
+    #[test]
+    fn hoist_mutate_shrinks_or_keeps_tree() {
+        let mut rng = ::rand::weak_rng();
+        let mut tg = TreeGen::full_ranged(&mut rng, 1, 4);
+        let mut tree = TestTree::child(&mut tg, 0);
+        let before = tree.count_nodes();
+        hoist_mutate(&mut tree, &mut rng);
+        assert!(tree.count_nodes() <= before);
+    }
+}
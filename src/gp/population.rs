@@ -0,0 +1,148 @@
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use gp::{Tree, TreeGen};
+#[cfg(feature = "rayon")]
+use gp::TreeGenMode;
+
+/// A generation of individuals sharing one species of `Tree`.
+#[derive(Clone, Debug)]
+pub struct Population<T: Tree> {
+    /// The individual trees making up this population.
+    pub individuals: Vec<T>,
+}
+
+/// Pure-value description of how to generate each individual, used by
+/// `Population::generate_par` to build a fresh `TreeGen` per individual.
+///
+/// Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+#[derive(Clone, Copy, Debug)]
+pub struct TreeGenConfig {
+    /// Which tree shape to generate. See `TreeGenMode`.
+    pub mode: TreeGenMode,
+    /// The minimum depth of trees to generate.
+    pub min_depth: usize,
+    /// The maximum depth of trees to generate.
+    pub max_depth: usize,
+}
+
+impl<T: Tree> Population<T> {
+    /// Generate a new population of `size` random individuals, threading
+    /// `tg` through each in turn.
+    ///
+    /// Note that `TreeGen::perfect`/`TreeGen::full_ranged` choose their
+    /// depth once at construction, so reusing one `tg` across the whole
+    /// population gives every individual the same depth; construct a fresh
+    /// `TreeGen` per individual if varied depths are wanted.
+    pub fn generate<R: Rng>(size: usize, tg: &mut TreeGen<R>) -> Population<T> {
+        let individuals = (0..size).map(|_| T::child(tg, 0)).collect();
+        Population { individuals }
+    }
+
+    /// Evaluate `fitness` against every individual, in order.
+    pub fn map_fitness<F, Fit>(&self, fitness: F) -> Vec<Fit>
+        where F: FnMut(&T) -> Fit
+    {
+        self.individuals.iter().map(fitness).collect()
+    }
+
+    /// Generate a new population of `size` random individuals in parallel
+    /// using `rayon`. Each individual gets its own `R`, built by calling
+    /// `make_rng(index)` — so callers seed however their `R` actually
+    /// supports (e.g. deriving a `[u32; 4]`/slice seed from a base seed and
+    /// the index), and the population stays reproducible regardless of how
+    /// the work happens to be scheduled across threads.
+    ///
+    /// Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn generate_par<R, F>(size: usize, config: TreeGenConfig, make_rng: F) -> Population<T>
+        where T: Send,
+              R: Rng + Send,
+              F: Fn(usize) -> R + Sync
+    {
+        let individuals = (0..size)
+            .into_par_iter()
+            .map(|index| {
+                let mut rng = make_rng(index);
+                let mut tg = TreeGen {
+                    rng: &mut rng,
+                    mode: config.mode,
+                    min_depth: config.min_depth,
+                    max_depth: config.max_depth,
+                };
+                T::child(&mut tg, 0)
+            })
+            .collect();
+        Population { individuals }
+    }
+
+    /// Evaluate `fitness` against every individual in parallel.
+    ///
+    /// Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn par_map_fitness<F, Fit>(&self, fitness: F) -> Vec<Fit>
+        where T: Sync,
+              F: Fn(&T) -> Fit + Sync,
+              Fit: Send
+    {
+        // Not `map(fitness)`: that requires `F: Send` (we only require `Sync`),
+        // since passing `fitness` itself imposes its own auto-trait bounds,
+        // while capturing it by reference in a closure only needs `&F: Send`.
+        #[allow(clippy::redundant_closure)]
+        self.individuals.par_iter().map(|individual| fitness(individual)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "rayon")]
+    use rand::SeedableRng;
+    use gp::test_support::TestTree;
+
+    #[test]
+    fn generate_produces_size_individuals() {
+        let mut rng = ::rand::weak_rng();
+        let mut tg = TreeGen::full_ranged(&mut rng, 1, 3);
+        let population: Population<TestTree> = Population::generate(10, &mut tg);
+        assert_eq!(population.individuals.len(), 10);
+    }
+
+    #[test]
+    fn map_fitness_visits_every_individual_in_order() {
+        let mut rng = ::rand::weak_rng();
+        let mut tg = TreeGen::full_ranged(&mut rng, 1, 3);
+        let population: Population<TestTree> = Population::generate(5, &mut tg);
+        let sizes = population.map_fitness(|tree| tree.count_nodes());
+        assert_eq!(sizes.len(), 5);
+        assert_eq!(sizes, population.individuals.iter().map(Tree::count_nodes).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn generate_par_produces_size_individuals() {
+        let config = TreeGenConfig {
+            mode: TreeGenMode::FullRanged(3),
+            min_depth: 1,
+            max_depth: 3,
+        };
+        let population: Population<TestTree> =
+            Population::generate_par(10, config, |index| ::rand::StdRng::from_seed(&[index]));
+        assert_eq!(population.individuals.len(), 10);
+        for individual in &population.individuals {
+            assert!(individual.depth() <= 3);
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_map_fitness_visits_every_individual() {
+        let mut rng = ::rand::weak_rng();
+        let mut tg = TreeGen::full_ranged(&mut rng, 1, 3);
+        let population: Population<TestTree> = Population::generate(5, &mut tg);
+        let sizes = population.par_map_fitness(Tree::count_nodes);
+        assert_eq!(sizes.len(), 5);
+    }
+}
@@ -0,0 +1,44 @@
+//! The `Tree` fixture shared by every test module under `gp`, so the five
+//! near-identical copies that used to live in `mod`, `gen`, `crossover`,
+//! `mutation` and `population` can't drift apart from each other.
+
+use rand::Rng;
+
+use gp::{Tree, TreeGen};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TestTree {
+    Leaf,
+    Branch(Vec<TestTree>),
+}
+
+impl Tree for TestTree {
+    fn children(&self) -> &[Self] {
+        match *self {
+            TestTree::Leaf => &[],
+            TestTree::Branch(ref children) => &children[..],
+        }
+    }
+
+    fn children_mut(&mut self) -> &mut [Self] {
+        match *self {
+            TestTree::Leaf => &mut [],
+            TestTree::Branch(ref mut children) => &mut children[..],
+        }
+    }
+
+    fn rand_terminal<R: Rng>(_rng: &mut R) -> Self {
+        TestTree::Leaf
+    }
+
+    fn rand_replacement<R: Rng>(&self, _rng: &mut R) -> Self {
+        match *self {
+            TestTree::Leaf => TestTree::Leaf,
+            TestTree::Branch(ref children) => TestTree::Branch(children.clone()),
+        }
+    }
+
+    fn rand_nonterminal<R: Rng>(tg: &mut TreeGen<R>, current_depth: usize) -> Self {
+        TestTree::Branch((0..2).map(|_| Tree::child(tg, current_depth + 1)).collect())
+    }
+}
@@ -17,12 +17,22 @@
         unused_import_braces, unused_qualifications)]
 
 extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "derive")]
+extern crate evco_derive;
 // #[cfg(test)]
 // extern crate quickcheck;
 
 /// Genetic Programming.
 pub mod gp;
 
+/// `#[derive(Tree)]`. See `evco_derive` for the attributes it understands.
+///
+/// Requires the `derive` feature.
+#[cfg(feature = "derive")]
+pub use evco_derive::Tree;
+
 #[cfg(test)]
 mod tests {
     #[test]
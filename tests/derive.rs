@@ -0,0 +1,78 @@
+//! End-to-end coverage for `#[derive(Tree)]`, requires the `derive` feature.
+
+#![cfg(feature = "derive")]
+
+extern crate evco;
+extern crate rand;
+
+use evco::gp::{Tree, TreeGen};
+use evco::Tree as TreeDerive;
+
+#[derive(Clone, Debug, TreeDerive)]
+enum Expr {
+    Constant,
+    #[tree(arity = 2, weight = 2)]
+    Add(Vec<Expr>),
+    #[tree(arity = 2)]
+    Mul(Vec<Expr>),
+    #[tree(arity = 1)]
+    Neg(Vec<Expr>),
+}
+
+#[test]
+fn terminal_proportion_is_fieldless_over_total_variants() {
+    // 1 terminal (Constant) out of 4 variants.
+    assert_eq!(Expr::TERMINAL_PROPORTION, 0.25);
+}
+
+#[test]
+fn children_reflects_each_variants_fields() {
+    assert_eq!(Expr::Constant.children().len(), 0);
+    assert_eq!(Expr::Add(vec![Expr::Constant, Expr::Constant]).children().len(), 2);
+    assert_eq!(Expr::Neg(vec![Expr::Constant]).children().len(), 1);
+}
+
+#[test]
+fn rand_terminal_only_ever_produces_the_terminal_variant() {
+    let mut rng = rand::weak_rng();
+    for _ in 0..200 {
+        assert!(matches!(Expr::rand_terminal(&mut rng), Expr::Constant));
+    }
+}
+
+#[test]
+fn rand_nonterminal_builds_children_matching_its_arity() {
+    let mut rng = rand::weak_rng();
+    for _ in 0..200 {
+        // A `TreeGen` whose chosen depth is `max_depth` forces the root to be
+        // a nonterminal, since depth `0` hasn't reached it yet.
+        let mut tg = TreeGen::perfect(&mut rng, 1, 1);
+        match Expr::child(&mut tg, 0) {
+            Expr::Add(ref children) | Expr::Mul(ref children) => assert_eq!(children.len(), 2),
+            Expr::Neg(ref children) => assert_eq!(children.len(), 1),
+            Expr::Constant => panic!("expected a nonterminal at depth 0 of a depth-1 tree"),
+        }
+    }
+}
+
+#[test]
+fn rand_replacement_preserves_arity_across_mismatched_arities() {
+    let mut rng = rand::weak_rng();
+    let two_children = Expr::Add(vec![Expr::Constant, Expr::Constant]);
+    for _ in 0..200 {
+        // `Neg` has arity 1, so a correct implementation never picks it here,
+        // even though it's a sibling nonterminal in the same enum.
+        match two_children.rand_replacement(&mut rng) {
+            Expr::Add(ref children) | Expr::Mul(ref children) => assert_eq!(children.len(), 2),
+            other => panic!("expected an arity-2 variant, got {:?}", other),
+        }
+    }
+
+    let one_child = Expr::Neg(vec![Expr::Constant]);
+    for _ in 0..200 {
+        match one_child.rand_replacement(&mut rng) {
+            Expr::Neg(ref children) => assert_eq!(children.len(), 1),
+            other => panic!("expected the arity-1 variant, got {:?}", other),
+        }
+    }
+}